@@ -1,10 +1,17 @@
-use gpio::{gpio_pin_new, GpioPin};
+#[cfg(feature = "std")]
+use gpio::{gpio_pin_new, gpio_pin_new_with, GpioBackend, GpioPin};
 
+#[cfg(feature = "std")]
 use std::error::Error;
+#[cfg(feature = "std")]
 use std::fmt;
+#[cfg(feature = "std")]
 use std::io::Error as IoError;
+#[cfg(feature = "std")]
 use std::io::ErrorKind as IoErrorKind;
+#[cfg(feature = "std")]
 use std::thread;
+#[cfg(feature = "std")]
 use std::time::{Duration, Instant};
 
 /*
@@ -13,24 +20,175 @@ use libc::{__errno_location, sched_get_priority_max, sched_getparam, sched_getsc
            sched_param, sched_setscheduler, SCHED_FIFO};
 */
 
+#[cfg(feature = "std")]
 const MINIMUM_CACHE: u64 = 1250; // miliseconds
+#[cfg(feature = "std")]
 const CACHE_ON_ERROR: u64 = 5; //seconds
 
+/// Pure DHT frame decode/checksum helpers: only integer arithmetic, no `std`
+/// (or even `alloc`) dependency, so they're reusable as-is from a `no_std`
+/// driver even though the rest of this crate (`DhtSensor`, `GpioPin`, etc.)
+/// still requires `std`.
+pub mod decode {
+    /// Minimum high-pulse width, in microseconds, that we classify as a `1`
+    /// bit. A `0` bit pulses high for ~28us and a `1` bit for ~70us, so 40us
+    /// sits comfortably between them.
+    pub const PULSE_WIDTH_THRESHOLD_US: u64 = 40;
+
+    /// Decode the 40 data bits of a DHT frame from the raw per-level cycle
+    /// counts gathered by cycle-counting reads.
+    pub fn decode_cycles(cycles: &[u32; 83]) -> [u8; 5] {
+        let mut data: [u8; 5] = [0; 5];
+        for i in 0..40 {
+            let low_cycle = cycles[2 * i + 3];
+            let high_cycle = cycles[2 * i + 4];
+
+            data[i / 8] <<= 1;
+            if high_cycle > low_cycle {
+                // High cycles are greater than 50us low cycle count, must be a 1.
+                data[i / 8] |= 1;
+            }
+            // Else high cycles are less than (or equal to, a weird case) the 50us low
+            // cycle count so this must be a zero.  Nothing needs to be changed in the
+            // stored data.
+        }
+        data
+    }
+
+    /// Decode the 40 data bits of a DHT frame from measured high-pulse widths
+    /// in microseconds, as gathered by pulse-width-timed reads.
+    pub fn decode_pulse_widths(widths_us: &[u64; 40], threshold_us: u64) -> [u8; 5] {
+        let mut data: [u8; 5] = [0; 5];
+        for i in 0..40 {
+            data[i / 8] <<= 1;
+            if widths_us[i] > threshold_us {
+                data[i / 8] |= 1;
+            }
+        }
+        data
+    }
+
+    /// Validate the checksum byte of a decoded 40-bit DHT frame.
+    pub fn checksum_ok(data: &[u8; 5]) -> bool {
+        data[4] as u16
+            == ((data[0] as u16 + data[1] as u16 + data[2] as u16 + data[3] as u16) & 0xFF)
+    }
+}
+
+pub use self::decode::{checksum_ok, decode_cycles, decode_pulse_widths, PULSE_WIDTH_THRESHOLD_US};
+
+/// Injectable time source for the DHT start/handshake sequence.
+///
+/// `DhtSensor` defaults to [`StdDelay`], which calls straight through to
+/// `std::thread::sleep`. Note this only covers the startup delay --
+/// `DhtSensor` still calls `std::time::Instant::now()` directly for the
+/// response/bit-timing deadlines, so it requires `std` regardless of which
+/// `DhtDelay` is plugged in.
+#[cfg(feature = "std")]
+pub trait DhtDelay {
+    /// Block for roughly `ms` milliseconds.
+    fn delay_ms(&mut self, ms: u32);
+}
+
+/// Default [`DhtDelay`] used by [`DhtSensor`], backed by `std::thread::sleep`.
+#[cfg(feature = "std")]
+pub struct StdDelay;
+
+#[cfg(feature = "std")]
+impl DhtDelay for StdDelay {
+    fn delay_ms(&mut self, ms: u32) {
+        thread::sleep(Duration::from_millis(ms as u64));
+    }
+}
+
 /// Determine DHT sensor types
-#[derive(Debug, Clone)]
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DhtType {
     DHT11,
+    /// I2C/single-wire sibling of the DHT11, sharing its 40-bit frame but
+    /// with an added decimal place on both readings (see [`DhtValue`]).
+    DHT12,
     DHT21,
     DHT22,
+    /// Wire-compatible with, and decoded as, a DHT22.
+    AM2301,
+    /// I2C only, not the single-wire DHT protocol this crate speaks.
+    /// [`DhtSensor::read`] returns an `Unsupported`-kind error for it
+    /// rather than attempting (and failing) a single-wire read.
+    SI7021,
+}
+
+#[cfg(feature = "std")]
+impl DhtType {
+    /// Numeric chip identifier (11/12/21/22), as commonly used for logging
+    /// and MQTT-style reporting, mirroring the espurna sensor layer.
+    pub fn chip_number(&self) -> u8 {
+        match *self {
+            DhtType::DHT11 => 11,
+            DhtType::DHT12 => 12,
+            DhtType::DHT21 => 21,
+            DhtType::DHT22 => 22,
+            DhtType::AM2301 => 22,
+            // Not a DHTxx part at all, so there's no DHTxx number to report.
+            DhtType::SI7021 => 0,
+        }
+    }
+}
+
+/// Timing profile for the start/handshake sequence of a DHT read.
+///
+/// These durations are tuned empirically per board and sensor model rather
+/// than fixed by the DHT protocol. The defaults in [`DhtTiming::for_type`]
+/// work for most setups; override them with [`DhtSensor::with_timing`] if
+/// reads are unreliable on a particular board.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct DhtTiming {
+    /// How long to release the line (drive high) before pulling it low to
+    /// start the handshake.
+    pub start_high: Duration,
+    /// How long to hold the line low to wake the sensor up.
+    pub start_low: Duration,
+    /// How long to wait for the sensor's low/high response pulses before
+    /// giving up.
+    pub response_timeout: Duration,
+    /// Overall deadline for reading all 40 data bits.
+    pub read_limit: Duration,
+}
+
+#[cfg(feature = "std")]
+impl DhtTiming {
+    /// Default timing profile for a given sensor type.
+    pub fn for_type(dht_type: &DhtType) -> DhtTiming {
+        match *dht_type {
+            // DHT11/DHT12 need a noticeably longer wake-up pulse than the
+            // AM23xx family DHT21/DHT22/AM2301 are built on.
+            DhtType::DHT11 | DhtType::DHT12 => DhtTiming {
+                start_high: Duration::from_millis(250),
+                start_low: Duration::from_millis(20),
+                response_timeout: Duration::from_micros(200),
+                read_limit: Duration::from_millis(10),
+            },
+            _ => DhtTiming {
+                start_high: Duration::from_millis(250),
+                start_low: Duration::from_millis(2),
+                response_timeout: Duration::from_micros(200),
+                read_limit: Duration::from_millis(10),
+            },
+        }
+    }
 }
 
 /// Represent readings from DHT* sensor .
+#[cfg(feature = "std")]
 #[derive(Debug)]
 pub struct DhtValue {
     dht_type: DhtType,
     value: [u8; 5],
 }
 
+#[cfg(feature = "std")]
 impl DhtValue {
     /// Return temperature readings in Fahrenheit.
     pub fn temperature_f(&self) -> f32 {
@@ -39,8 +197,22 @@ impl DhtValue {
 
     /// Return temperature readings in Celcius.
     pub fn temperature(&self) -> f32 {
-        match &self.dht_type {
-            DHT11 => self.value[2] as f32,
+        match self.dht_type {
+            // Newer DHT11/DHT12 units put a decimal fraction in the second
+            // temperature byte (value[3]) and the sign in the high bit of
+            // value[2]; humidity likewise has its fraction in value[1].
+            // Older DHT11 units
+            // always send a zero fractional byte, so this also covers them.
+            DhtType::DHT11 | DhtType::DHT12 => {
+                let mut v: f32 = (self.value[2] & 0x7F) as f32 + (self.value[3] as f32) * 0.1;
+                if self.value[2] & 0x80 > 0 {
+                    v *= -1.0;
+                }
+                v
+            }
+            // DHT21/DHT22/AM2301 share the same higher-resolution encoding.
+            // SI7021 never reaches here -- DhtSensor::read_raw rejects it
+            // before a frame is decoded.
             _ => {
                 let mut v: f32 = (self.value[2] & 0x7F) as f32;
                 v = (v * 256.0 + self.value[3] as f32) * 0.1;
@@ -54,8 +226,8 @@ impl DhtValue {
 
     /// Return humidity readins in percents.
     pub fn humidity(&self) -> f32 {
-        match &self.dht_type {
-            DHT11 => self.value[0] as f32,
+        match self.dht_type {
+            DhtType::DHT11 | DhtType::DHT12 => self.value[0] as f32 + (self.value[1] as f32) * 0.1,
             _ => {
                 let mut v: f32 = self.value[0] as f32;
                 v = (v * 256.0 + self.value[1] as f32) * 0.1;
@@ -77,38 +249,89 @@ impl DhtValue {
     }
 }
 
+#[cfg(feature = "std")]
 pub struct DhtSensor {
     pin: u8,
     dht_type: DhtType,
     gpio: Box<GpioPin>,
     last_read: Instant,
     value: [u8; 5],
+    timed_decode: bool,
+    delay: Box<DhtDelay>,
+    timing: DhtTiming,
 }
 
 /// Ideas about DHT reading sensors was found here:
 /// - https://github.com/adafruit/DHT-sensor-library/blob/master/DHT.cpp
 /// - https://github.com/adafruit/Adafruit_Python_DHT/blob/master/source/Raspberry_Pi/pi_dht_read.c
+#[cfg(feature = "std")]
 impl DhtSensor {
     pub fn new(pin: u8, dht_type: DhtType) -> Result<DhtSensor, Box<Error>> {
         let gpio = gpio_pin_new(pin as u32)?;
         DhtSensor::new_common(pin, dht_type, gpio)
     }
 
+    /// Build a `DhtSensor` against an explicit [`GpioBackend`] instead of
+    /// this build's cfg-selected default (see [`gpio_pin_new`]). This is how
+    /// a non-Raspberry-Pi Linux SBC picks `Sysfs` or `Gpiod` explicitly.
+    pub fn new_with_backend(
+        pin: u8,
+        dht_type: DhtType,
+        backend: GpioBackend,
+    ) -> Result<DhtSensor, Box<Error>> {
+        let gpio = gpio_pin_new_with(backend, pin as u32)?;
+        DhtSensor::new_common(pin, dht_type, gpio)
+    }
+
     fn new_common(
         pin: u8,
         dht_type: DhtType,
         mut gpio: Box<GpioPin>,
     ) -> Result<DhtSensor, Box<Error>> {
         gpio.direction_input();
+        let timing = DhtTiming::for_type(&dht_type);
         Ok(DhtSensor {
             pin: pin,
             dht_type: dht_type,
             gpio: gpio,
             last_read: Instant::now() - Duration::from_secs(1000),
             value: [0; 5],
+            timed_decode: false,
+            delay: Box::new(StdDelay),
+            timing: timing,
         })
     }
 
+    /// Use a custom [`DhtDelay`] for the start/handshake sequence instead of
+    /// the default [`StdDelay`].
+    pub fn with_delay(mut self, delay: Box<DhtDelay>) -> DhtSensor {
+        self.delay = delay;
+        self
+    }
+
+    /// Override the start/handshake timing profile. See [`DhtTiming`] for
+    /// what each duration controls and [`DhtTiming::for_type`] for the
+    /// defaults this replaces.
+    pub fn with_timing(mut self, timing: DhtTiming) -> DhtSensor {
+        self.timing = timing;
+        self
+    }
+
+    /// Decode bits by measuring pulse width in microseconds instead of
+    /// counting busy-wait cycles.
+    ///
+    /// Cycle counting (the default, see [`DhtSensor::read_raw`]) is sensitive
+    /// to CPU frequency scaling because the number of loop iterations per
+    /// microsecond isn't fixed across boards or governors. Measuring the
+    /// actual high-pulse duration avoids that calibration entirely, at the
+    /// cost of a couple of `Instant::now()` calls per bit. Enable it when
+    /// readings are unreliable on a given board, or to benchmark both
+    /// strategies against each other.
+    pub fn with_timed_decode(mut self, enabled: bool) -> DhtSensor {
+        self.timed_decode = enabled;
+        self
+    }
+
     /// Try read sensor untill attempts limits will be reached.
     /// Repeat reading only on errorrs with little delay between reads.
     ///
@@ -177,18 +400,37 @@ impl DhtSensor {
     /// Return result and data readed from sensor.
     /// Even on errors data can be not empty
     fn read_raw(&mut self) -> Result<DhtValue, IoError> {
+        if self.dht_type == DhtType::SI7021 {
+            // SI7021 is an I2C part, not a single-wire DHT; bail out with a
+            // typed error instead of bit-banging the line and reporting a
+            // misleading checksum failure.
+            return Err(IoError::new(
+                IoErrorKind::Unsupported,
+                format!("SI7021 uses I2C, not the single-wire DHT protocol"),
+            ));
+        }
+
+        if self.timed_decode {
+            self.read_raw_timed()
+        } else {
+            self.read_raw_cycles()
+        }
+    }
+
+    /// Raw read from DHT sensor using busy-counted cycles per level.
+    /// Return result and data readed from sensor.
+    /// Even on errors data can be not empty
+    fn read_raw_cycles(&mut self) -> Result<DhtValue, IoError> {
         // Initialize variables
         let mut err: Option<IoError> = None;
-        let mut data: [u8; 5] = [0; 5]; // Set 40 bits of received data to zero.
         let mut cycles: [u32; 83] = [0; 83];
-        let read_limit = Instant::now() + Duration::from_millis(10);
 
         // Send start signal.  See DHT datasheet for full signal diagram:
         //   http://www.adafruit.com/datasheets/Digital%20humidity%20and%20temperature%20sensor%20AM2302.pdf
         // Go into high impedence state to let pull-up raise data line level and
         // start the reading process.
         self.gpio.direction_output(1);
-        thread::sleep(Duration::from_millis(250));
+        self.delay.delay_ms(self.timing.start_high.as_millis() as u32);
 
         // Try to raise thread priority
         /*
@@ -222,7 +464,7 @@ impl DhtSensor {
         */
         // Time critical section begins
         {
-            let end_sleep = Instant::now() + Duration::from_millis(20);
+            let end_sleep = Instant::now() + self.timing.start_low;
             // Voltage  level  from  high to  low.
             // This process must take at least 18ms to ensure DHT’s detection of MCU's signal.
             self.gpio.set_low();
@@ -252,6 +494,11 @@ impl DhtSensor {
 
             // READ to cycles[3+] as low level and cycles[4+] as high level
 
+            // Only start the read deadline now: start_high/start_low above
+            // already take ~250ms on their own, so computing this before them
+            // leaves it expired before the loop below even starts.
+            let read_limit = Instant::now() + self.timing.read_limit;
+
             let mut i = 0;
             let mut x = 0;
             while i < 83 {
@@ -289,20 +536,99 @@ impl DhtSensor {
         // Inspect pulses and determine which ones are 0 (high state cycle count < low
         // state cycle count), or 1 (high state cycle count > low state cycle count).
         // We skip first 3 values because there is not data there
-        for i in 0..40 {
-            let low_cycle = cycles[2 * i + 3];
-            let high_cycle = cycles[2 * i + 4];
+        let data = decode_cycles(&cycles);
 
-            data[i / 8] <<= 1;
-            if high_cycle > low_cycle {
-                // High cycles are greater than 50us low cycle count, must be a 1.
-                data[i / 8] |= 1;
+        self.finish_read(data)
+    }
+
+    /// Raw read from DHT sensor decoding bits from measured pulse width in
+    /// microseconds instead of busy-wait cycle counts.
+    ///
+    /// Each of the 40 data bits is sent as a ~50us low pulse followed by a
+    /// variable length high pulse: ~28us for a `0`, ~70us for a `1`. Instead
+    /// of counting loop iterations per level, this spins on the line and
+    /// timestamps the rising/falling edges directly, so the result doesn't
+    /// depend on a per-board cycle-count calibration.
+    fn read_raw_timed(&mut self) -> Result<DhtValue, IoError> {
+        let mut widths_us: [u64; 40] = [0; 40];
+
+        self.gpio.direction_output(1);
+        self.delay.delay_ms(self.timing.start_high.as_millis() as u32);
+
+        {
+            let end_sleep = Instant::now() + self.timing.start_low;
+            self.gpio.set_low();
+            while Instant::now() < end_sleep {}
+
+            self.gpio.direction_input();
+
+            // Response: ~80us low followed by ~80us high. We don't need the
+            // width, just wait for the sensor to release the line before the
+            // 40 data bits start. Before that, the pull-up holds the line
+            // high for ~20-40us until the sensor pulls it low.
+            let pull_up_deadline = Instant::now() + self.timing.response_timeout;
+            while self.gpio.read() == 1 {
+                if Instant::now() > pull_up_deadline {
+                    return Err(IoError::new(
+                        IoErrorKind::TimedOut,
+                        format!("Timed out waiting for sensor response"),
+                    ));
+                }
+            }
+            let response_deadline = Instant::now() + self.timing.response_timeout;
+            while self.gpio.read() == 0 {
+                if Instant::now() > response_deadline {
+                    return Err(IoError::new(
+                        IoErrorKind::TimedOut,
+                        format!("Timed out waiting for sensor response"),
+                    ));
+                }
+            }
+            while self.gpio.read() == 1 {
+                if Instant::now() > response_deadline {
+                    return Err(IoError::new(
+                        IoErrorKind::TimedOut,
+                        format!("Timed out waiting for sensor response"),
+                    ));
+                }
+            }
+
+            // Only start the read deadline now: start_high/start_low above
+            // already take ~250ms on their own, so computing this before them
+            // leaves it expired before the first bit is even read.
+            let read_limit = Instant::now() + self.timing.read_limit;
+
+            for i in 0..40 {
+                // Each bit starts with a ~50us low pulse we don't care about.
+                while self.gpio.read() == 0 {
+                    if Instant::now() > read_limit {
+                        return Err(IoError::new(
+                            IoErrorKind::TimedOut,
+                            format!("Reading time exceeded {:?}", self.timing.read_limit),
+                        ));
+                    }
+                }
+
+                let start = Instant::now();
+                while self.gpio.read() == 1 {
+                    if Instant::now() > read_limit {
+                        return Err(IoError::new(
+                            IoErrorKind::TimedOut,
+                            format!("Reading time exceeded {:?}", self.timing.read_limit),
+                        ));
+                    }
+                }
+                widths_us[i] = (Instant::now() - start).as_micros() as u64;
             }
-            // Else high cycles are less than (or equal to, a weird case) the 50us low
-            // cycle count so this must be a zero.  Nothing needs to be changed in the
-            // stored data.
         }
 
+        let data = decode_pulse_widths(&widths_us, PULSE_WIDTH_THRESHOLD_US);
+        self.finish_read(data)
+    }
+
+    /// Validate the checksum of a freshly decoded 40-bit frame and, on
+    /// success, cache it as the sensor's last known good reading.
+    fn finish_read(&mut self, data: [u8; 5]) -> Result<DhtValue, IoError> {
         #[cfg(feature = "debug_trace")]
         {
             print!("DHT readings: ");
@@ -315,9 +641,7 @@ impl DhtSensor {
         }
 
         // Check we read 40 bits and that the checksum matches.
-        if data[4] as u16
-            == ((data[0] as u16 + data[1] as u16 + data[2] as u16 + data[3] as u16) & 0xFF)
-        {
+        if checksum_ok(&data) {
             self.value = data;
             self.last_read = Instant::now();
             Ok(DhtValue {
@@ -331,6 +655,7 @@ impl DhtSensor {
     }
 }
 
+#[cfg(feature = "std")]
 impl fmt::Debug for DhtSensor {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "DHT ({:?} pin:{})", self.dht_type, self.pin)
@@ -342,6 +667,7 @@ impl fmt::Debug for DhtSensor {
 ///
 /// * `temp` - Temperature in Celsius of Fahrenheit
 /// * `fahrenheit` - Define input and output temperature format (true for Fahrenheit)
+#[cfg(feature = "std")]
 fn heat_index(temp: f32, humidity: f32, fahrenheit: bool) -> f32 {
     let mut temperature = temp;
     if !fahrenheit {
@@ -372,3 +698,119 @@ fn heat_index(temp: f32, humidity: f32, fahrenheit: bool) -> f32 {
         (hi - 32.0) * 0.55555
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{checksum_ok, decode_pulse_widths, DhtTiming, DhtType, DhtValue, PULSE_WIDTH_THRESHOLD_US};
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 0.01,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn dht11_whole_number_reading() {
+        let v = DhtValue {
+            dht_type: DhtType::DHT11,
+            value: [45, 0, 26, 0, 45 + 26],
+        };
+        assert_close(v.humidity(), 45.0);
+        assert_close(v.temperature(), 26.0);
+    }
+
+    #[test]
+    fn dht11_fractional_reading() {
+        let v = DhtValue {
+            dht_type: DhtType::DHT11,
+            value: [52, 5, 26, 5, 52 + 5 + 26 + 5],
+        };
+        assert_close(v.humidity(), 52.5);
+        assert_close(v.temperature(), 26.5);
+    }
+
+    #[test]
+    fn dht11_negative_temperature() {
+        let v = DhtValue {
+            dht_type: DhtType::DHT11,
+            value: [40, 0, 0x85, 5, 40 + 0x85 + 5],
+        };
+        assert_close(v.temperature(), -5.5);
+    }
+
+    #[test]
+    fn dht22_reading_unaffected() {
+        // humidity raw = 567 -> 56.7%; temperature raw = 257 -> 25.7C
+        let v = DhtValue {
+            dht_type: DhtType::DHT22,
+            value: [2, 55, 1, 1, 2 + 55 + 1 + 1],
+        };
+        assert_close(v.humidity(), 56.7);
+        assert_close(v.temperature(), 25.7);
+    }
+
+    #[test]
+    fn am2301_uses_dht22_decode_path() {
+        let v = DhtValue {
+            dht_type: DhtType::AM2301,
+            value: [2, 55, 1, 1, 2 + 55 + 1 + 1],
+        };
+        assert_close(v.humidity(), 56.7);
+        assert_close(v.temperature(), 25.7);
+    }
+
+    #[test]
+    fn dht12_shares_dht11_decode_path() {
+        let v = DhtValue {
+            dht_type: DhtType::DHT12,
+            value: [52, 5, 0x85, 5, 52 + 5 + 0x85 + 5],
+        };
+        assert_close(v.humidity(), 52.5);
+        assert_close(v.temperature(), -5.5);
+    }
+
+    #[test]
+    fn decode_pulse_widths_reads_0x55_and_checksum() {
+        // 0x55 = 01010101, alternating 0/1 bits; checksum byte = 0x55 + 0.
+        let mut widths_us = [0u64; 40];
+        for i in 0..8 {
+            let bit = (0x55u8 >> (7 - i)) & 1;
+            let width = if bit == 1 {
+                PULSE_WIDTH_THRESHOLD_US + 30
+            } else {
+                PULSE_WIDTH_THRESHOLD_US - 12
+            };
+            widths_us[i] = width;
+            widths_us[32 + i] = width;
+        }
+        let data = decode_pulse_widths(&widths_us, PULSE_WIDTH_THRESHOLD_US);
+        assert_eq!(data[0], 0x55);
+        assert_eq!(data[1], 0);
+        assert_eq!(data[2], 0);
+        assert_eq!(data[3], 0);
+        assert_eq!(data[4], 0x55);
+        assert!(checksum_ok(&data));
+    }
+
+    #[test]
+    fn timing_defaults_give_dht11_a_longer_wake_up_pulse() {
+        let dht11 = DhtTiming::for_type(&DhtType::DHT11);
+        let dht22 = DhtTiming::for_type(&DhtType::DHT22);
+        assert!(dht11.start_low > dht22.start_low);
+        assert_eq!(DhtTiming::for_type(&DhtType::DHT12).start_low, dht11.start_low);
+        assert_eq!(DhtTiming::for_type(&DhtType::AM2301).start_low, dht22.start_low);
+    }
+
+    #[test]
+    fn chip_number_matches_common_dht_numbering() {
+        assert_eq!(DhtType::DHT11.chip_number(), 11);
+        assert_eq!(DhtType::DHT12.chip_number(), 12);
+        assert_eq!(DhtType::DHT21.chip_number(), 21);
+        assert_eq!(DhtType::DHT22.chip_number(), 22);
+        assert_eq!(DhtType::AM2301.chip_number(), 22);
+        assert_eq!(DhtType::SI7021.chip_number(), 0);
+    }
+}
@@ -1,69 +1,150 @@
 /**
  * Common GPIO interface.
- * 
+ *
  * @see https://github.com/torvalds/linux/blob/v4.4/include/linux/gpio/consumer.h
  * @see https://www.kernel.org/doc/Documentation/gpio/consumer.txt
  */
 
-//#[cfg(feature = "use_rppal")]
+#[cfg(feature = "use_rppal")]
 use rppal;
 
+#[cfg(feature = "use_libc")]
+use libc;
+
+// embedded-hal 0.2.x gates InputPin/OutputPin behind its own "unproven"
+// Cargo feature, so a Cargo.toml enabling "use_embedded_hal" needs
+// `embedded-hal = { version = "0.2", features = ["unproven"] }`.
+#[cfg(feature = "use_embedded_hal")]
+use embedded_hal::digital::v2::{InputPin, OutputPin};
+
 use std::io::{Error,ErrorKind};
+#[cfg(feature = "use_rppal")]
 use std::error::Error as ErrorStd;
+use std::fs;
+#[cfg(feature = "use_libc")]
+use std::fs::OpenOptions;
+#[cfg(feature = "use_libc")]
+use std::fs::File;
+use std::path::Path;
+#[cfg(feature = "use_libc")]
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::thread;
+use std::time::Duration;
 
+/// Selects which underlying mechanism a [`GpioPin`] uses to talk to the
+/// hardware.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpioBackend {
+    /// Memory-mapped access via the `rppal` crate. Raspberry Pi only, but
+    /// the fastest option where it's available.
+    Rppal,
+    /// Legacy `/sys/class/gpio` sysfs interface. Works on any Linux board,
+    /// including older kernels that predate the character-device API, but
+    /// is slow (every access is a file read/write) and was deprecated
+    /// upstream in favour of `Gpiod`.
+    Sysfs,
+    /// Modern `/dev/gpiochipN` character-device line-request ioctl
+    /// interface (see the module-level kernel doc links above). Works on
+    /// any recent Linux SBC, not just the Raspberry Pi.
+    Gpiod,
+}
+
+/// Create a [`GpioPin`] using the default backend for this build: `rppal`
+/// when its feature is enabled, `gpiod` otherwise.
 pub fn gpio_pin_new(pin_number: u32) -> Result<Box<GpioPin>, Error> {
-    let g = GpioPinRppal::new(pin_number)?;
-    Ok(Box::new(g))
+    #[cfg(feature = "use_rppal")]
+    {
+        gpio_pin_new_with(GpioBackend::Rppal, pin_number)
+    }
+    #[cfg(not(feature = "use_rppal"))]
+    {
+        gpio_pin_new_with(GpioBackend::Gpiod, pin_number)
+    }
+}
+
+/// Create a [`GpioPin`] using a specific backend.
+///
+/// This is how boards that aren't a Raspberry Pi pick a backend that
+/// actually works on their kernel: `Gpiod` for anything running a modern
+/// (>=4.8) kernel exposing `/dev/gpiochipN`, `Sysfs` for older boards stuck
+/// on the legacy `/sys/class/gpio` interface.
+pub fn gpio_pin_new_with(backend: GpioBackend, pin_number: u32) -> Result<Box<GpioPin>, Error> {
+    match backend {
+        #[cfg(feature = "use_rppal")]
+        GpioBackend::Rppal => {
+            let g = GpioPinRppal::new(pin_number)?;
+            Ok(Box::new(g) as Box<GpioPin>)
+        }
+        #[cfg(not(feature = "use_rppal"))]
+        GpioBackend::Rppal => Err(Error::new(
+            ErrorKind::Other,
+            "the \"use_rppal\" feature is disabled in this build",
+        )),
+        GpioBackend::Sysfs => {
+            let g = GpioPinSysfs::new(pin_number)?;
+            Ok(Box::new(g) as Box<GpioPin>)
+        }
+        #[cfg(feature = "use_libc")]
+        GpioBackend::Gpiod => {
+            let g = GpioPinGpiod::new(pin_number)?;
+            Ok(Box::new(g) as Box<GpioPin>)
+        }
+        #[cfg(not(feature = "use_libc"))]
+        GpioBackend::Gpiod => Err(Error::new(
+            ErrorKind::Other,
+            "the \"use_libc\" feature is disabled in this build",
+        )),
+    }
 }
 
 /// This trait represents single GPIO pin for spinlock-Safe GPIO Access
 /// Most GPIO controllers can be accessed with memory read/write instructions. Those
 /// don't need to sleep, and can safely be done from inside hard (non-threaded) IRQ handlers and similar contexts.
 pub trait GpioPin {
-    
+
     /// Setting pin direction as input without activation of any pull up/down resitors.
-    /// 
+    ///
     /// Keep in mind that get/set calls don't return errors and since misconfiguration is possible.
     fn direction_input(&mut self) -> Result<(), Error>;
-    
+
     /// Setting pin direction as output.
-    /// 
+    ///
     /// For output GPIOs, the value provided becomes the initial output value.
     /// This helps avoid signal glitching during system startup.
-    /// 
+    ///
     /// Keep in mind that get/set calls don't return errors and since misconfiguration is possible.
     fn direction_output(&mut self, value: i32) -> Result<(), Error>;
-    
+
     /// Set pin value.
-    /// The values are boolean, zero for low, nonzero for high. 
-    /// The get/set calls do not return errors because "invalid GPIO" should have been reported earlier from gpiod_direction_*(). 
+    /// The values are boolean, zero for low, nonzero for high.
+    /// The get/set calls do not return errors because "invalid GPIO" should have been reported earlier from gpiod_direction_*().
     /// Also, using these calls for GPIOs that can't safely be accessed without sleeping (see below) is an error.
     fn set(&mut self, value: i32);
 
     /// Read pin value.
-    /// The values are boolean, zero for low, nonzero for high. 
-    /// When reading the value of an output pin, the value returned should be what's seen on the pin. 
+    /// The values are boolean, zero for low, nonzero for high.
+    /// When reading the value of an output pin, the value returned should be what's seen on the pin.
     /// That won't always match the specified output value, because of issues including open-drain signaling and output latencies.
-    /// The get/set calls do not return errors because "invalid GPIO" should have been reported earlier from direction_*(). 
+    /// The get/set calls do not return errors because "invalid GPIO" should have been reported earlier from direction_*().
     /// However, note that not all platforms can read the value of output pins; those that can't should always return zero.
     /// Also, using these calls for GPIOs that can't safely be accessed without sleeping (see below) is an error.
     fn read(&mut self) -> i32;
-    
+
     /// Set pin to hight level
     fn set_high(&mut self);
-    
+
     /// Set pin value to low level
     fn set_low(&mut self);
 }
 
-//#[cfg(feature = "use_rppal")]
+#[cfg(feature = "use_rppal")]
 struct GpioPinRppal {
     pin: u8,
     init_mode: rppal::gpio::Mode,
     rppal: rppal::gpio::Gpio,
 }
 
-//#[cfg(feature = "use_rppal")]
+#[cfg(feature = "use_rppal")]
 impl GpioPinRppal {
     fn new(pin: u32) -> Result<GpioPinRppal, Error> {
         let mut pp = rppal::gpio::Gpio::new().map_err(|e| {
@@ -81,7 +162,7 @@ impl GpioPinRppal {
     }
 }
 
-//#[cfg(feature = "use_rppal")]
+#[cfg(feature = "use_rppal")]
 impl GpioPin for GpioPinRppal {
     fn direction_input(&mut self) -> Result<(), Error> {
         self.rppal.set_mode(self.pin, rppal::gpio::Mode::Input);
@@ -120,8 +201,304 @@ impl GpioPin for GpioPinRppal {
 }
 
 
+#[cfg(feature = "use_rppal")]
 impl Drop for GpioPinRppal {
     fn drop(&mut self) {
         self.rppal.set_mode(self.pin, self.init_mode);
     }
 }
+
+/// Legacy `/sys/class/gpio` backend, for kernels that don't expose the
+/// `/dev/gpiochipN` character device yet.
+struct GpioPinSysfs {
+    pin: u32,
+}
+
+impl GpioPinSysfs {
+    fn new(pin: u32) -> Result<GpioPinSysfs, Error> {
+        let direction_path = format!("/sys/class/gpio/gpio{}/direction", pin);
+        if !Path::new(&direction_path).exists() {
+            fs::write("/sys/class/gpio/export", pin.to_string())?;
+            // udev creates the gpioN/direction and gpioN/value attribute
+            // files asynchronously after export, so poll for them instead of
+            // assuming they exist the instant export returns.
+            let mut waited = 0;
+            while !Path::new(&direction_path).exists() {
+                if waited >= 100 {
+                    return Err(Error::new(
+                        ErrorKind::TimedOut,
+                        format!("gpio{} was exported but {} never appeared", pin, direction_path),
+                    ));
+                }
+                thread::sleep(Duration::from_millis(10));
+                waited += 1;
+            }
+        }
+        Ok(GpioPinSysfs { pin: pin })
+    }
+
+    fn write_attr(&self, attr: &str, value: &str) -> Result<(), Error> {
+        fs::write(format!("/sys/class/gpio/gpio{}/{}", self.pin, attr), value)
+    }
+
+    fn read_attr(&self, attr: &str) -> Result<String, Error> {
+        fs::read_to_string(format!("/sys/class/gpio/gpio{}/{}", self.pin, attr))
+    }
+}
+
+impl GpioPin for GpioPinSysfs {
+    fn direction_input(&mut self) -> Result<(), Error> {
+        self.write_attr("direction", "in")
+    }
+
+    fn direction_output(&mut self, value: i32) -> Result<(), Error> {
+        self.write_attr("direction", if value > 0 { "high" } else { "low" })
+    }
+
+    fn set(&mut self, value: i32) {
+        let _ = self.write_attr("value", if value > 0 { "1" } else { "0" });
+    }
+
+    fn set_high(&mut self) {
+        self.set(1);
+    }
+
+    fn set_low(&mut self) {
+        self.set(0);
+    }
+
+    fn read(&mut self) -> i32 {
+        self.read_attr("value")
+            .ok()
+            .and_then(|v| v.trim().parse::<i32>().ok())
+            .unwrap_or(0)
+    }
+}
+
+impl Drop for GpioPinSysfs {
+    fn drop(&mut self) {
+        let _ = fs::write("/sys/class/gpio/unexport", self.pin.to_string());
+    }
+}
+
+/// Raw ioctl bindings for the `/dev/gpiochipN` line-request API described in
+/// `<linux/gpio.h>`. There's no maintained binding crate in this project's
+/// dependency tree yet, so the handful of structs/constants we need are
+/// reproduced here directly from the kernel uAPI header.
+#[cfg(feature = "use_libc")]
+mod gpiod_ioctl {
+    use libc::{c_char, c_int, c_ulong};
+    use std::os::unix::io::RawFd;
+
+    const GPIOHANDLE_REQUEST_INPUT: u32 = 1 << 0;
+    const GPIOHANDLE_REQUEST_OUTPUT: u32 = 1 << 1;
+
+    /// `struct gpiohandle_request` from `<linux/gpio.h>`.
+    #[repr(C)]
+    struct GpioHandleRequest {
+        line_offsets: [u32; 64],
+        flags: u32,
+        default_values: [u8; 64],
+        consumer_label: [c_char; 32],
+        lines: u32,
+        fd: c_int,
+    }
+
+    /// `struct gpiohandle_data` from `<linux/gpio.h>`.
+    #[repr(C)]
+    struct GpioHandleData {
+        values: [u8; 64],
+    }
+
+    // Computed from `_IOWR(0xB4, nr, type)` against the structs above.
+    const GPIO_GET_LINEHANDLE_IOCTL: c_ulong = 0xc16cb403;
+    const GPIOHANDLE_GET_LINE_VALUES_IOCTL: c_ulong = 0xc040b408;
+    const GPIOHANDLE_SET_LINE_VALUES_IOCTL: c_ulong = 0xc040b409;
+
+    /// Request exclusive control of a single line on an already-open chip,
+    /// returning the line's own file descriptor.
+    pub fn request_line(chip_fd: RawFd, offset: u32, output: bool, default_value: u8) -> Result<RawFd, i32> {
+        let mut req: GpioHandleRequest = unsafe { std::mem::zeroed() };
+        req.line_offsets[0] = offset;
+        req.lines = 1;
+        req.flags = if output {
+            GPIOHANDLE_REQUEST_OUTPUT
+        } else {
+            GPIOHANDLE_REQUEST_INPUT
+        };
+        req.default_values[0] = default_value;
+        for (i, b) in b"gpio-sensors\0".iter().enumerate() {
+            req.consumer_label[i] = *b as c_char;
+        }
+
+        let rc = unsafe { libc::ioctl(chip_fd, GPIO_GET_LINEHANDLE_IOCTL, &mut req) };
+        if rc < 0 {
+            return Err(unsafe { *libc::__errno_location() });
+        }
+        Ok(req.fd)
+    }
+
+    pub fn get_value(line_fd: RawFd) -> Result<u8, i32> {
+        let mut data: GpioHandleData = unsafe { std::mem::zeroed() };
+        let rc = unsafe { libc::ioctl(line_fd, GPIOHANDLE_GET_LINE_VALUES_IOCTL, &mut data) };
+        if rc < 0 {
+            return Err(unsafe { *libc::__errno_location() });
+        }
+        Ok(data.values[0])
+    }
+
+    pub fn set_value(line_fd: RawFd, value: u8) -> Result<(), i32> {
+        let mut data: GpioHandleData = unsafe { std::mem::zeroed() };
+        data.values[0] = value;
+        let rc = unsafe { libc::ioctl(line_fd, GPIOHANDLE_SET_LINE_VALUES_IOCTL, &mut data) };
+        if rc < 0 {
+            return Err(unsafe { *libc::__errno_location() });
+        }
+        Ok(())
+    }
+}
+
+/// Modern `/dev/gpiochipN` character-device backend, using the consumer
+/// line-request ioctl interface rather than the deprecated sysfs tree.
+///
+/// The DHT protocol needs to flip a single line between input and output at
+/// runtime, but a requested line handle's direction is fixed for its
+/// lifetime, so [`GpioPinGpiod::request`] closes and re-requests the line
+/// every time direction changes.
+#[cfg(feature = "use_libc")]
+struct GpioPinGpiod {
+    chip: File,
+    offset: u32,
+    line_fd: Option<RawFd>,
+}
+
+#[cfg(feature = "use_libc")]
+impl GpioPinGpiod {
+    fn new(pin: u32) -> Result<GpioPinGpiod, Error> {
+        let chip = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open("/dev/gpiochip0")?;
+        Ok(GpioPinGpiod {
+            chip: chip,
+            offset: pin,
+            line_fd: None,
+        })
+    }
+
+    fn request(&mut self, output: bool, default_value: u8) -> Result<(), Error> {
+        if let Some(fd) = self.line_fd.take() {
+            unsafe { libc::close(fd) };
+        }
+        let fd = gpiod_ioctl::request_line(self.chip.as_raw_fd(), self.offset, output, default_value)
+            .map_err(Error::from_raw_os_error)?;
+        self.line_fd = Some(fd);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "use_libc")]
+impl GpioPin for GpioPinGpiod {
+    fn direction_input(&mut self) -> Result<(), Error> {
+        self.request(false, 0)
+    }
+
+    fn direction_output(&mut self, value: i32) -> Result<(), Error> {
+        self.request(true, if value > 0 { 1 } else { 0 })
+    }
+
+    fn set(&mut self, value: i32) {
+        if let Some(fd) = self.line_fd {
+            let _ = gpiod_ioctl::set_value(fd, if value > 0 { 1 } else { 0 });
+        }
+    }
+
+    fn set_high(&mut self) {
+        self.set(1);
+    }
+
+    fn set_low(&mut self) {
+        self.set(0);
+    }
+
+    fn read(&mut self) -> i32 {
+        self.line_fd
+            .and_then(|fd| gpiod_ioctl::get_value(fd).ok())
+            .map(|v| v as i32)
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(feature = "use_libc")]
+impl Drop for GpioPinGpiod {
+    fn drop(&mut self) {
+        if let Some(fd) = self.line_fd {
+            unsafe { libc::close(fd) };
+        }
+    }
+}
+
+/// Adapter from a single `embedded-hal` pin to [`GpioPin`].
+///
+/// The DHT protocol needs to flip one pin between input and output at
+/// runtime, which `embedded-hal` itself has no notion of -- it only has
+/// separate `InputPin`/`OutputPin` traits. This adapter assumes `P` is
+/// wired as an open-drain pin implementing both: releasing the line (input
+/// mode) is done by driving it high and letting the external pull-up do the
+/// rest, whereas the other backends in this module switch the line to a
+/// real input.
+///
+/// Note this only adapts the `GpioPin` trait itself; `DhtSensor` still uses
+/// `std::time::Instant` for its deadlines, so plugging this in doesn't make
+/// `DhtSensor` usable on a `no_std` target.
+#[cfg(feature = "use_embedded_hal")]
+pub struct GpioPinEmbeddedHal<P> {
+    pin: P,
+}
+
+#[cfg(feature = "use_embedded_hal")]
+impl<P> GpioPinEmbeddedHal<P>
+where
+    P: InputPin + OutputPin,
+{
+    pub fn new(pin: P) -> GpioPinEmbeddedHal<P> {
+        GpioPinEmbeddedHal { pin: pin }
+    }
+}
+
+#[cfg(feature = "use_embedded_hal")]
+impl<P> GpioPin for GpioPinEmbeddedHal<P>
+where
+    P: InputPin + OutputPin,
+{
+    fn direction_input(&mut self) -> Result<(), Error> {
+        self.pin
+            .set_high()
+            .map_err(|_| Error::new(ErrorKind::Other, "embedded-hal pin error"))
+    }
+
+    fn direction_output(&mut self, value: i32) -> Result<(), Error> {
+        self.set(value);
+        Ok(())
+    }
+
+    fn set(&mut self, value: i32) {
+        let _ = if value > 0 {
+            self.pin.set_high()
+        } else {
+            self.pin.set_low()
+        };
+    }
+
+    fn set_high(&mut self) {
+        self.set(1);
+    }
+
+    fn set_low(&mut self) {
+        self.set(0);
+    }
+
+    fn read(&mut self) -> i32 {
+        self.pin.is_high().map(|v| v as i32).unwrap_or(0)
+    }
+}
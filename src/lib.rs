@@ -1,11 +1,18 @@
 #![crate_type = "lib"]
 #![crate_name = "gpio_sensors"]
+// "std" is on by default; build with --no-default-features to get the
+// no_std-friendly parts of `dht` (currently just `dht::decode`) without the
+// rest of the crate, which still needs std (file I/O, threads, Instant).
+#![cfg_attr(not(feature = "std"), no_std)]
 
 #[cfg(feature = "use_libc")]
 extern crate libc;
-//#[cfg(feature = "use_rppal")]
+#[cfg(feature = "use_rppal")]
 extern crate rppal;
+#[cfg(feature = "use_embedded_hal")]
+extern crate embedded_hal;
 
+#[cfg(feature = "std")]
 pub mod gpio;
 pub mod dht;
 